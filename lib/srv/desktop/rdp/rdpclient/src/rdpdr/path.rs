@@ -12,8 +12,47 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 use std::ffi::{CString, NulError};
+use std::fmt;
 
-/// WindowsPath is a String that we assume to be in the form
+/// Error returned when a path's bytes are not well-formed UTF-8.
+///
+/// RDP device redirection delivers filenames as UTF-16, which can contain
+/// unpaired surrogates; the corresponding bytes are not valid UTF-8. We keep
+/// the raw bytes losslessly and surface this error from the fallible
+/// conversions instead of panicking or lossily mangling the name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Utf8Error {
+    valid_up_to: usize,
+}
+
+impl Utf8Error {
+    /// The index in the path bytes up to which valid UTF-8 was decoded.
+    pub fn valid_up_to(&self) -> usize {
+        self.valid_up_to
+    }
+}
+
+impl fmt::Display for Utf8Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "path contains invalid UTF-8 starting at byte {}",
+            self.valid_up_to
+        )
+    }
+}
+
+impl std::error::Error for Utf8Error {}
+
+impl From<std::str::Utf8Error> for Utf8Error {
+    fn from(e: std::str::Utf8Error) -> Utf8Error {
+        Utf8Error {
+            valid_up_to: e.valid_up_to(),
+        }
+    }
+}
+
+/// WindowsPath is a byte string that we assume to be in the form
 /// of a traditional DOS path:
 ///
 /// https://docs.microsoft.com/en-us/dotnet/standard/io/file-path-formats
@@ -24,88 +63,514 @@ use std::ffi::{CString, NulError};
 /// r"\Program Files\Custom Utilities\StringFinder.exe": An absolute path from the root of the current drive.
 ///
 /// r"2018\January.xlsx": A relative path to a file in a subdirectory of the current directory.
+///
+/// The path is held as raw bytes rather than a `String` so that non-UTF-8
+/// filenames round-trip losslessly; use [`WindowsPath::to_str`] for a fallible
+/// view as a `&str`.
 #[derive(Debug, Clone)]
 pub struct WindowsPath {
-    pub path: String,
+    pub path: Vec<u8>,
 }
 
 impl WindowsPath {
+    /// Builds a path from raw bytes, taking them verbatim.
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> WindowsPath {
+        Self { path: bytes.into() }
+    }
+
+    /// Returns the raw path bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.path
+    }
+
+    /// Returns the path as a `&str`, or a [`Utf8Error`] if the bytes are not
+    /// well-formed UTF-8.
+    pub fn to_str(&self) -> Result<&str, Utf8Error> {
+        std::str::from_utf8(&self.path).map_err(Utf8Error::from)
+    }
+
     pub fn len(&self) -> u32 {
         self.path.len() as u32
     }
+
+    /// Lexically normalizes the path, collapsing `.` and `..` components
+    /// without consulting the filesystem. See [`normalize_lexical`] for the
+    /// clamping semantics; here the DOS separator `\` is used.
+    ///
+    /// The drive/UNC prefix is parsed off before the walk so it acts as the
+    /// clamp boundary: a `..` that would pop past the drive or share is
+    /// dropped and flagged, rather than silently eating the prefix. It is then
+    /// re-attached, so `C:\..\foo` normalizes to `C:\foo` with `clamped = true`.
+    /// An extended-length `\\?\` marker is not re-attached: the result uses
+    /// the plain `C:\` or `\\host\share` form.
+    pub fn normalize(&self) -> (WindowsPath, bool) {
+        let parsed = self.parse();
+        let (body, clamped) = normalize_lexical(&parsed.components.join(&b'\\'), b'\\');
+
+        let mut out = Vec::new();
+        if let Some((host, share)) = &parsed.unc {
+            out.extend_from_slice(br"\\");
+            out.extend_from_slice(host);
+            out.push(b'\\');
+            out.extend_from_slice(share);
+            if !body.is_empty() {
+                out.push(b'\\');
+            }
+            out.extend_from_slice(&body);
+        } else if let Some(drive) = parsed.drive {
+            out.push(drive as u8);
+            out.push(b':');
+            out.push(b'\\');
+            out.extend_from_slice(&body);
+        } else {
+            out = body;
+        }
+        (WindowsPath::from_bytes(out), clamped)
+    }
+
+    /// Parses the DOS path into its prefix components, recognizing drive
+    /// letters, UNC host/share pairs, and `\\?\` / `\\?\UNC\` extended-length
+    /// prefixes. See [`ParsedWindowsPath`].
+    pub fn parse(&self) -> ParsedWindowsPath {
+        ParsedWindowsPath::parse(&self.path)
+    }
+
+    /// Returns the drive letter (e.g. `C`) if this path is drive-qualified.
+    pub fn drive(&self) -> Option<char> {
+        self.parse().drive
+    }
+
+    /// Returns `true` if this path is a UNC path (`\\host\share\...` or the
+    /// `\\?\UNC\host\share\...` extended form).
+    pub fn is_unc(&self) -> bool {
+        self.parse().unc.is_some()
+    }
+
+    /// Iterates over the non-empty `\`-separated components of the path.
+    ///
+    /// This is the DOS-separator counterpart of [`UnixPath::components`]; it
+    /// operates lexically on the raw path and does not strip a drive/UNC
+    /// prefix (use [`WindowsPath::parse`] for that) or interpret `.`/`..`.
+    pub fn components(&self) -> impl Iterator<Item = &[u8]> {
+        lexical_components(&self.path, b'\\')
+    }
+
+    /// Returns the final component of the path, if there is one that is not
+    /// `.` or `..`.
+    pub fn file_name(&self) -> Option<&[u8]> {
+        let (start, end) = lexical_file_name_range(&self.path, b'\\')?;
+        Some(&self.path[start..end])
+    }
+
+    /// Returns the path without its final component, or `None` if the path
+    /// is empty, consists only of separators, or ends in `.` or `..`.
+    pub fn parent(&self) -> Option<WindowsPath> {
+        lexical_parent(&self.path, b'\\').map(WindowsPath::from_bytes)
+    }
+
+    /// Returns the extension of [`WindowsPath::file_name`], if any: the portion
+    /// after the final `.`, provided the name does not begin with that `.`.
+    pub fn extension(&self) -> Option<&[u8]> {
+        lexical_extension(self.file_name()?)
+    }
+
+    /// Returns a copy of the path with [`WindowsPath::file_name`]'s extension
+    /// replaced by `extension`. An empty `extension` strips the extension.
+    pub fn with_extension(&self, extension: impl AsRef<[u8]>) -> WindowsPath {
+        WindowsPath::from_bytes(lexical_with_extension(&self.path, b'\\', extension.as_ref()))
+    }
+
+    /// Returns a copy of the path with `name` appended as a child component,
+    /// inserting a `\` separator as needed.
+    pub fn join(&self, name: impl AsRef<[u8]>) -> WindowsPath {
+        WindowsPath::from_bytes(lexical_join(&self.path, b'\\', name.as_ref()))
+    }
+
+    /// Returns `true` if `base` is a component-wise prefix of this path.
+    pub fn starts_with(&self, base: impl AsRef<[u8]>) -> bool {
+        lexical_starts_with(&self.path, base.as_ref(), b'\\')
+    }
 }
 
 impl From<String> for WindowsPath {
     fn from(path: String) -> WindowsPath {
-        Self { path }
+        Self {
+            path: path.into_bytes(),
+        }
     }
 }
 
-/// UnixPath is a String that we assume to be in the form of a
+/// The decomposed form of a [`WindowsPath`], produced by [`WindowsPath::parse`].
+///
+/// RDP redirection can hand us drive-qualified paths (`C:\foo`), UNC paths
+/// (`\\server\share\file`), and extended-length paths (`\\?\C:\foo`). Parsing
+/// the prefix up front lets redirection code route per drive or share instead
+/// of doing brittle string surgery on the raw path. Components are kept as
+/// bytes so non-UTF-8 names survive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedWindowsPath {
+    /// The drive letter, if the path was drive-qualified.
+    pub drive: Option<char>,
+    /// The `(host, share)` pair, if the path was UNC.
+    pub unc: Option<(Vec<u8>, Vec<u8>)>,
+    /// The remaining path components, separators stripped.
+    pub components: Vec<Vec<u8>>,
+}
+
+impl ParsedWindowsPath {
+    fn parse(path: &[u8]) -> ParsedWindowsPath {
+        let mut rest = path;
+        let mut unc = false;
+
+        // Strip a verbatim extended-length prefix first, then fall back to the
+        // plain `\\` UNC prefix.
+        if let Some(s) = rest.strip_prefix(br"\\?\UNC\".as_slice()) {
+            rest = s;
+            unc = true;
+        } else if let Some(s) = rest.strip_prefix(br"\\?\".as_slice()) {
+            rest = s;
+        } else if let Some(s) = rest.strip_prefix(br"\\".as_slice()) {
+            rest = s;
+            unc = true;
+        }
+
+        let mut drive = None;
+        let mut host_share = None;
+
+        if unc {
+            // host\share\tail...
+            let mut parts = rest.splitn(3, |&b| b == b'\\');
+            let host = parts.next().unwrap_or_default().to_vec();
+            let share = parts.next().unwrap_or_default().to_vec();
+            rest = parts.next().unwrap_or_default();
+            host_share = Some((host, share));
+        } else if rest.len() >= 2 && rest[0].is_ascii_alphabetic() && rest[1] == b':' {
+            // Optional `X:` drive qualifier.
+            drive = Some(rest[0] as char);
+            rest = &rest[2..];
+        }
+
+        let components = rest
+            .split(|&b| b == b'\\')
+            .filter(|c| !c.is_empty())
+            .map(<[u8]>::to_vec)
+            .collect();
+
+        ParsedWindowsPath {
+            drive,
+            unc: host_share,
+            components,
+        }
+    }
+}
+
+/// UnixPath is a byte string that we assume to be in the form of a
 /// Unix Path, qualified by the qualifications laid out in RFD 0067
 ///
 /// https://github.com/gravitational/teleport/blob/master/rfd/0067-desktop-access-file-system-sharing.md
+///
+/// Like [`WindowsPath`] it holds raw bytes so that non-UTF-8 filenames
+/// round-trip losslessly.
 #[derive(Debug, Clone)]
 pub struct UnixPath {
-    pub path: String,
+    pub path: Vec<u8>,
 }
 
 impl UnixPath {
+    /// Builds a path from raw bytes, taking them verbatim.
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> UnixPath {
+        Self { path: bytes.into() }
+    }
+
+    /// Returns the raw path bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.path
+    }
+
+    /// Returns the path as a `&str`, or a [`Utf8Error`] if the bytes are not
+    /// well-formed UTF-8.
+    pub fn to_str(&self) -> Result<&str, Utf8Error> {
+        std::str::from_utf8(&self.path).map_err(Utf8Error::from)
+    }
+
     /// This function will create a CString from a UnixPath.
     ///
     /// # Errors
     ///
     /// This function will return an error if the UnixPath contains
-    /// any characters that can't be handled by CString::new().
+    /// an interior NUL byte, which CString::new() cannot represent.
     pub fn to_cstring(&self) -> Result<CString, NulError> {
         CString::new(self.path.clone())
     }
 
+    /// Renders the path as an RFC 8089 `file://` URI with RFC 3986
+    /// percent-encoding, suitable for unambiguous (and clickable) display in
+    /// logs, audit events, or UIs.
+    ///
+    /// Every byte outside the unreserved set (`A–Z a–z 0–9 - . _ ~`) and the
+    /// `/` separator is encoded as `%XX` with uppercase hex; this covers
+    /// spaces, backslashes, and non-ASCII UTF-8 continuation bytes. Because a
+    /// [`UnixPath`] is root-relative after conversion, a leading `/` is
+    /// prepended so the URI always names an absolute path.
+    pub fn to_file_uri(&self) -> String {
+        const UNRESERVED: &[u8] = b"-._~";
+        let mut uri = String::from("file:///");
+        for &byte in &self.path {
+            match byte {
+                b'/' => uri.push('/'),
+                b if b.is_ascii_alphanumeric() || UNRESERVED.contains(&b) => {
+                    uri.push(b as char)
+                }
+                b => uri.push_str(&format!("%{b:02X}")),
+            }
+        }
+        uri
+    }
+
     pub fn len(&self) -> u32 {
         self.path.len() as u32
     }
 
-    pub fn last(&self) -> Option<&str> {
-        self.path.split('/').last()
+    pub fn last(&self) -> Option<&[u8]> {
+        self.path.split(|&b| b == b'/').last()
+    }
+
+    /// Lexically normalizes the path, collapsing `.` and `..` components
+    /// without consulting the filesystem. See [`normalize_lexical`] for the
+    /// clamping semantics.
+    ///
+    /// The boolean in the returned tuple is `true` when at least one `..`
+    /// was clamped at the root, so callers can log or deny a suspicious
+    /// (potentially directory-traversing) request from the RDP client.
+    pub fn normalize(&self) -> (UnixPath, bool) {
+        let (path, clamped) = normalize_lexical(&self.path, b'/');
+        (UnixPath::from_bytes(path), clamped)
+    }
+
+    /// Iterates over the non-empty `/`-separated components of the path.
+    ///
+    /// This is a lexical view of the RDP-constrained grammar (forward-slash
+    /// separated, root-relative after conversion); it does not touch the
+    /// filesystem and does not interpret `.`/`..` (run [`UnixPath::normalize`]
+    /// first if that is required).
+    pub fn components(&self) -> impl Iterator<Item = &[u8]> {
+        lexical_components(&self.path, b'/')
+    }
+
+    /// Returns the final component of the path, if there is one that is not
+    /// `.` or `..`.
+    pub fn file_name(&self) -> Option<&[u8]> {
+        let (start, end) = lexical_file_name_range(&self.path, b'/')?;
+        Some(&self.path[start..end])
+    }
+
+    /// Returns the path without its final component, or `None` if the path
+    /// is empty, consists only of separators, or ends in `.` or `..`.
+    pub fn parent(&self) -> Option<UnixPath> {
+        lexical_parent(&self.path, b'/').map(UnixPath::from_bytes)
+    }
+
+    /// Returns the extension of [`UnixPath::file_name`], if any: the portion
+    /// after the final `.`, provided the name does not begin with that `.`.
+    pub fn extension(&self) -> Option<&[u8]> {
+        lexical_extension(self.file_name()?)
+    }
+
+    /// Returns a copy of the path with [`UnixPath::file_name`]'s extension
+    /// replaced by `extension`. An empty `extension` strips the extension.
+    pub fn with_extension(&self, extension: impl AsRef<[u8]>) -> UnixPath {
+        UnixPath::from_bytes(lexical_with_extension(&self.path, b'/', extension.as_ref()))
+    }
+
+    /// Returns a copy of the path with `name` appended as a child component,
+    /// inserting a separator as needed. Joining onto an empty (root) path
+    /// yields the bare name, so a directory entry can be built from its
+    /// parent plus a name without string concatenation.
+    pub fn join(&self, name: impl AsRef<[u8]>) -> UnixPath {
+        UnixPath::from_bytes(lexical_join(&self.path, b'/', name.as_ref()))
+    }
+
+    /// Returns `true` if `base` is a component-wise prefix of this path. This
+    /// matches whole components, so `a/b` starts with `a` but not with `a/b2`.
+    pub fn starts_with(&self, base: impl AsRef<[u8]>) -> bool {
+        lexical_starts_with(&self.path, base.as_ref(), b'/')
     }
 }
 
 impl From<&WindowsPath> for UnixPath {
     fn from(p: &WindowsPath) -> UnixPath {
-        Self::from(to_unix_path(&p.path))
+        // Drop any drive/UNC prefix and rejoin the component list as a clean,
+        // root-relative Unix path (see Teleport RFD 0067).
+        Self::from_bytes(p.parse().components.join(&b'/'))
     }
 }
 
 impl From<String> for UnixPath {
     fn from(path: String) -> UnixPath {
-        Self { path }
+        Self {
+            path: path.into_bytes(),
+        }
     }
 }
 
-/// Converts a String from the type of path that's sent to us by RDP
-/// into a unix-style path, as specified in Teleport RFD 0067:
-///
-/// https://github.com/gravitational/teleport/blob/master/rfd/0067-desktop-access-file-system-sharing.md
-fn to_unix_path(rdp_path: &str) -> String {
-    // Convert r"\" to "/"
-    let mut cleaned = rdp_path.replace('\\', "/");
+impl From<&UnixPath> for WindowsPath {
+    /// Converts a server-side Unix path back into the DOS form an RDP client
+    /// expects: `/` separators become `\`, and a leading `\` is prepended to
+    /// mark the result as rooted on the current drive (our [`UnixPath`] is
+    /// root-relative after conversion).
+    ///
+    /// Bytes that are illegal in a Windows filename — `< > : " | ? *`, a `\`
+    /// inside a name, and the C0 control range (`0x00`–`0x1F`) — are
+    /// percent-encoded as `%XX` with
+    /// uppercase hex so the name stays representable, rather than silently
+    /// dropped or corrupted. The escape character `%` (0x25) is itself encoded
+    /// so the mapping stays injective and a `%XX` decoder can reverse it
+    /// unambiguously.
+    fn from(p: &UnixPath) -> WindowsPath {
+        const ILLEGAL: &[u8] = b"<>:\"|?*\\";
+        let mut out = Vec::with_capacity(p.path.len() + 1);
+        out.push(b'\\');
+        for &byte in &p.path {
+            match byte {
+                b'/' => out.push(b'\\'),
+                b if b < 0x20 || b == b'%' || ILLEGAL.contains(&b) => {
+                    out.extend_from_slice(format!("%{b:02X}").as_bytes());
+                }
+                b => out.push(b),
+            }
+        }
+        WindowsPath::from_bytes(out)
+    }
+}
 
-    // If the string started with r"\", just remove it
-    if cleaned.starts_with('/') {
-        crop_first_n_letters(&mut cleaned, 1);
+/// Lexically collapses `.` and `..` components of a `sep`-separated path,
+/// returning the rejoined path and whether any `..` was clamped.
+///
+/// The walk pushes each ordinary component onto a stack, skipping empty
+/// segments and `.`. A `..` pops the stack only when it is non-empty;
+/// because a clamped `..` is dropped rather than pushed, the stack top is
+/// never itself a `..` boundary, so the result can never ascend above the
+/// root. This is a purely lexical "clamp" and deliberately does NOT resolve
+/// symlinks, as the `std::path` RFC warns against OS-style normalization.
+///
+/// An all-`..` input therefore normalizes to the empty (root) path. Trailing
+/// separators are not preserved: they produce an empty final component that
+/// is dropped like any other.
+fn normalize_lexical(path: &[u8], sep: u8) -> (Vec<u8>, bool) {
+    let mut stack: Vec<&[u8]> = Vec::new();
+    let mut clamped = false;
+    for component in path.split(|&b| b == sep) {
+        match component {
+            b"" | b"." => continue,
+            b".." => {
+                // The stack never holds a `..`, so a non-empty stack always
+                // has a poppable ordinary component on top.
+                if stack.pop().is_none() {
+                    clamped = true;
+                }
+            }
+            component => stack.push(component),
+        }
     }
+    (stack.join(&sep), clamped)
+}
 
-    cleaned
+// Lexical component helpers shared by `UnixPath` and `WindowsPath`, each
+// parameterized by the grammar's separator so the two types share one
+// implementation (mirroring `normalize_lexical`). All operate purely on
+// bytes and never touch the filesystem.
+
+/// Iterates the non-empty `sep`-separated components of `path`.
+fn lexical_components(path: &[u8], sep: u8) -> impl Iterator<Item = &[u8]> {
+    path.split(move |&b| b == sep).filter(|c| !c.is_empty())
 }
 
-/// Crops the first n letters off of a String (in-place).
-fn crop_first_n_letters(s: &mut String, n: usize) {
-    match s.char_indices().nth(n) {
-        Some((pos, _)) => {
-            s.drain(..pos);
+/// Byte range `[start, end)` of the final ordinary component of `path`, or
+/// `None` when the path is empty, consists only of separators, or its last
+/// component is `.`/`..`. Located by the last separator among the non-empty
+/// components so a trailing separator does not corrupt the offset.
+fn lexical_file_name_range(path: &[u8], sep: u8) -> Option<(usize, usize)> {
+    let mut start = 0;
+    let mut last = None;
+    for (i, &b) in path.iter().enumerate() {
+        if b == sep {
+            if i > start {
+                last = Some((start, i));
+            }
+            start = i + 1;
         }
-        None => {
-            s.clear();
+    }
+    if path.len() > start {
+        last = Some((start, path.len()));
+    }
+    let (s, e) = last?;
+    match &path[s..e] {
+        b"." | b".." => None,
+        _ => Some((s, e)),
+    }
+}
+
+/// Extension of a file name: the portion after the final `.`, unless the name
+/// begins with that `.` (a dotfile) or has none.
+fn lexical_extension(name: &[u8]) -> Option<&[u8]> {
+    match name.iter().rposition(|&b| b == b'.') {
+        Some(0) | None => None,
+        Some(pos) => Some(&name[pos + 1..]),
+    }
+}
+
+/// `path` with its final component removed and the joining separator trimmed.
+fn lexical_parent(path: &[u8], sep: u8) -> Option<Vec<u8>> {
+    let (start, _) = lexical_file_name_range(path, sep)?;
+    let mut end = start;
+    while end > 0 && path[end - 1] == sep {
+        end -= 1;
+    }
+    Some(path[..end].to_vec())
+}
+
+/// `path` with the final component's extension replaced by `ext` (stripped if
+/// `ext` is empty). A path with no file name is returned unchanged.
+fn lexical_with_extension(path: &[u8], sep: u8, ext: &[u8]) -> Vec<u8> {
+    let (name_start, name_end) = match lexical_file_name_range(path, sep) {
+        Some(range) => range,
+        None => return path.to_vec(),
+    };
+    let name = &path[name_start..name_end];
+    let stem_len = match name.iter().rposition(|&b| b == b'.') {
+        Some(0) | None => name.len(),
+        Some(pos) => pos,
+    };
+    let mut out = path[..name_start].to_vec();
+    out.extend_from_slice(&name[..stem_len]);
+    if !ext.is_empty() {
+        out.push(b'.');
+        out.extend_from_slice(ext);
+    }
+    out
+}
+
+/// `path` with `name` appended as a child, inserting `sep` as needed.
+fn lexical_join(path: &[u8], sep: u8, name: &[u8]) -> Vec<u8> {
+    let mut out = path.to_vec();
+    if !out.is_empty() && !out.ends_with(&[sep]) && !name.is_empty() {
+        out.push(sep);
+    }
+    out.extend_from_slice(name);
+    out
+}
+
+/// `true` when `base` is a component-wise prefix of `path`.
+fn lexical_starts_with(path: &[u8], base: &[u8], sep: u8) -> bool {
+    let mut theirs = lexical_components(base, sep);
+    let mut ours = lexical_components(path, sep);
+    loop {
+        match theirs.next() {
+            None => return true,
+            Some(b) => match ours.next() {
+                Some(c) if c == b => continue,
+                _ => return false,
+            },
         }
     }
 }
@@ -114,13 +579,221 @@ fn crop_first_n_letters(s: &mut String, n: usize) {
 mod tests {
     use super::*;
 
+    fn to_unix(p: &str) -> Vec<u8> {
+        UnixPath::from(&WindowsPath::from(p.to_string())).path
+    }
+
     #[test]
     fn test_to_unix_path() {
-        assert_eq!(to_unix_path(r"\"), "");
-        assert_eq!(to_unix_path(r"\desktop.ini"), "desktop.ini");
+        assert_eq!(to_unix(r"\"), b"");
+        assert_eq!(to_unix(r"\desktop.ini"), b"desktop.ini");
+        assert_eq!(
+            to_unix(r"\test_directory\desktop.ini"),
+            b"test_directory/desktop.ini"
+        );
+        assert_eq!(to_unix(r"2018\January.xlsx"), b"2018/January.xlsx");
+    }
+
+    #[test]
+    fn test_windows_path_parse() {
+        // Extended-length, drive-qualified.
+        let p = WindowsPath::from(r"\\?\C:\Users\file.txt".to_string());
+        let parsed = p.parse();
+        assert_eq!(parsed.drive, Some('C'));
+        assert_eq!(parsed.unc, None);
+        assert_eq!(parsed.components, vec![b"Users".to_vec(), b"file.txt".to_vec()]);
+        assert_eq!(p.drive(), Some('C'));
+        assert!(!p.is_unc());
+        assert_eq!(UnixPath::from(&p).path, b"Users/file.txt");
+
+        // UNC.
+        let p = WindowsPath::from(r"\\host\share\dir\f".to_string());
+        let parsed = p.parse();
+        assert_eq!(parsed.drive, None);
         assert_eq!(
-            to_unix_path(r"\test_directory\desktop.ini"),
-            "test_directory/desktop.ini"
+            parsed.unc,
+            Some((b"host".to_vec(), b"share".to_vec()))
         );
+        assert_eq!(parsed.components, vec![b"dir".to_vec(), b"f".to_vec()]);
+        assert!(p.is_unc());
+        assert_eq!(UnixPath::from(&p).path, b"dir/f");
+
+        // Extended-length UNC.
+        let p = WindowsPath::from(r"\\?\UNC\host\share\dir\f".to_string());
+        assert_eq!(
+            p.parse().unc,
+            Some((b"host".to_vec(), b"share".to_vec()))
+        );
+        assert!(p.is_unc());
+
+        // Plain drive-qualified and relative forms.
+        assert_eq!(WindowsPath::from(r"C:\foo".to_string()).drive(), Some('C'));
+        let rel = WindowsPath::from(r"2018\January.xlsx".to_string());
+        assert_eq!(rel.drive(), None);
+        assert!(!rel.is_unc());
+    }
+
+    #[test]
+    fn test_to_file_uri() {
+        assert_eq!(
+            UnixPath::from("desktop.ini".to_string()).to_file_uri(),
+            "file:///desktop.ini"
+        );
+        assert_eq!(
+            UnixPath::from("My Documents/a b.txt".to_string()).to_file_uri(),
+            "file:///My%20Documents/a%20b.txt"
+        );
+        // Non-ASCII filenames are encoded byte-by-byte as UTF-8.
+        assert_eq!(
+            UnixPath::from("café/αρχείο".to_string()).to_file_uri(),
+            "file:///caf%C3%A9/%CE%B1%CF%81%CF%87%CE%B5%CE%AF%CE%BF"
+        );
+    }
+
+    #[test]
+    fn test_unix_path_normalize() {
+        let cases: &[(&str, &[u8], bool)] = &[
+            ("test_directory/desktop.ini", b"test_directory/desktop.ini", false),
+            ("a/./b", b"a/b", false),
+            ("a//b", b"a/b", false),
+            ("a/b/../c", b"a/c", false),
+            ("a/b/", b"a/b", false),
+            // `..` that would escape the root is clamped away.
+            ("../../etc/passwd", b"etc/passwd", true),
+            ("a/../../b", b"b", true),
+            ("..", b"", true),
+            ("../..", b"", true),
+        ];
+        for (input, want, want_clamped) in cases {
+            let (got, clamped) = UnixPath::from(input.to_string()).normalize();
+            assert_eq!(&got.path, want, "normalizing {input:?}");
+            assert_eq!(clamped, *want_clamped, "clamp flag for {input:?}");
+        }
+    }
+
+    #[test]
+    fn test_windows_path_normalize() {
+        let (got, clamped) = WindowsPath::from(r"foo\..\..\bar".to_string()).normalize();
+        assert_eq!(got.path, b"bar");
+        assert!(clamped);
+
+        // A drive-qualified `..` must not eat the drive: the drive is the clamp
+        // boundary, so the traversal is flagged and the drive is preserved.
+        let (got, clamped) = WindowsPath::from(r"C:\..\foo".to_string()).normalize();
+        assert_eq!(got.path, br"C:\foo");
+        assert!(clamped);
+    }
+
+    #[test]
+    fn test_byte_round_trip() {
+        // Bytes that are not valid UTF-8 (an unpaired UTF-16 surrogate encoded
+        // naively as a lone 0xED 0xA0 0x80 sequence) survive losslessly.
+        let raw = b"dir/\xED\xA0\x80.txt";
+        let p = UnixPath::from_bytes(raw.to_vec());
+        assert_eq!(p.as_bytes(), raw);
+        let err = p.to_str().expect_err("should not be valid UTF-8");
+        assert_eq!(err.valid_up_to(), 4);
+    }
+
+    #[test]
+    fn test_path_manipulation() {
+        let p = UnixPath::from("a/b/file.txt".to_string());
+
+        assert_eq!(
+            p.components().collect::<Vec<_>>(),
+            vec![b"a".as_slice(), b"b", b"file.txt"]
+        );
+        assert_eq!(p.file_name(), Some(b"file.txt".as_slice()));
+        assert_eq!(p.extension(), Some(b"txt".as_slice()));
+        assert_eq!(p.parent().unwrap().path, b"a/b");
+
+        // A child is built from parent + name without touching the separator.
+        assert_eq!(p.parent().unwrap().join("other.txt").path, b"a/b/other.txt");
+        // Joining onto the root yields the bare name.
+        assert_eq!(UnixPath::from_bytes(Vec::new()).join("entry").path, b"entry");
+
+        assert_eq!(p.with_extension("md").path, b"a/b/file.md");
+        assert_eq!(p.with_extension("").path, b"a/b/file");
+
+        // Dotfiles have no extension.
+        assert_eq!(
+            UnixPath::from(".bashrc".to_string()).extension(),
+            None
+        );
+
+        assert!(p.starts_with("a/b"));
+        assert!(!p.starts_with("a/b2"));
+        assert!(p.starts_with(""));
+
+        // A trailing separator (common on RDP directory paths) must not shift
+        // the final-component boundary: parent/with_extension locate it by the
+        // last separator, not by `len - file_name.len()`.
+        let dir = UnixPath::from("a/b/".to_string());
+        assert_eq!(dir.file_name(), Some(b"b".as_slice()));
+        assert_eq!(dir.parent().unwrap().path, b"a");
+        assert_eq!(dir.with_extension("md").path, b"a/b.md");
+    }
+
+    #[test]
+    fn test_windows_path_manipulation() {
+        let p = WindowsPath::from(r"a\b\file.txt".to_string());
+
+        assert_eq!(
+            p.components().collect::<Vec<_>>(),
+            vec![b"a".as_slice(), b"b", b"file.txt"]
+        );
+        assert_eq!(p.file_name(), Some(b"file.txt".as_slice()));
+        assert_eq!(p.extension(), Some(b"txt".as_slice()));
+        assert_eq!(p.parent().unwrap().path, br"a\b");
+        assert_eq!(p.parent().unwrap().join("other.txt").path, br"a\b\other.txt");
+        assert_eq!(p.with_extension("md").path, br"a\b\file.md");
+
+        // Trailing separator does not corrupt the boundary.
+        let dir = WindowsPath::from(r"a\b\".to_string());
+        assert_eq!(dir.parent().unwrap().path, b"a");
+        assert_eq!(dir.with_extension("md").path, br"a\b.md");
+
+        assert!(p.starts_with(r"a\b"));
+        assert!(!p.starts_with(r"a\b2"));
+    }
+
+    #[test]
+    fn test_unix_to_windows_round_trip() {
+        // The absolute drive-rooted form from the type docs is stable across
+        // WindowsPath -> UnixPath -> WindowsPath.
+        for dos in [
+            r"\Program Files\Custom Utilities\StringFinder.exe",
+            r"\desktop.ini",
+        ] {
+            let win = WindowsPath::from(dos.to_string());
+            let unix = UnixPath::from(&win);
+            let back = WindowsPath::from(&unix);
+            assert_eq!(back.path, dos.as_bytes(), "round-tripping {dos:?}");
+        }
+
+        // A relative path is normalized to the rooted DOS form.
+        let unix = UnixPath::from("2018/January.xlsx".to_string());
+        assert_eq!(WindowsPath::from(&unix).path, br"\2018\January.xlsx");
+    }
+
+    #[test]
+    fn test_unix_to_windows_escapes_illegal() {
+        let unix = UnixPath::from("a:b?c/d".to_string());
+        // `:` and `?` are illegal in Windows names and are percent-encoded.
+        assert_eq!(WindowsPath::from(&unix).path, br"\a%3Ab%3Fc\d");
+
+        // A literal `%` is itself escaped so the encoding stays injective:
+        // `a%41b` must not collapse to the decoding of `a%41b`.
+        let unix = UnixPath::from("a%41b".to_string());
+        assert_eq!(WindowsPath::from(&unix).path, br"\a%2541b");
+    }
+
+    #[test]
+    fn test_to_cstring_rejects_interior_nul() {
+        let p = UnixPath::from_bytes(b"a\0b".to_vec());
+        assert!(p.to_cstring().is_err());
+
+        let p = UnixPath::from_bytes(b"a/b".to_vec());
+        assert_eq!(p.to_cstring().unwrap().as_bytes(), b"a/b");
     }
 }